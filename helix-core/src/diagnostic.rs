@@ -55,8 +55,124 @@ pub struct Diagnostic {
     pub data: Option<serde_json::Value>,
 }
 
-// TODO turn this into an enum + feature flag when lsp becomes optional
-pub type DiagnosticProvider = LanguageServerId;
+/// Identifies where a [`Diagnostic`] came from.
+///
+/// This used to be a plain `LanguageServerId` alias, but diagnostics can
+/// also originate from sources that aren't language servers at all, for
+/// example an in-editor spell checker or a TODO/FIXME scanner. Keying
+/// diagnostics on this type instead lets a document hold diagnostics from
+/// several providers at once and clear/replace one provider's diagnostics
+/// without touching anyone else's.
+///
+/// Note this is no longer `Copy`: the `Other` variant owns a `String`, so
+/// call sites that held onto a `DiagnosticProvider` by value and relied on
+/// implicit copies need `.clone()` instead. There are no other call sites
+/// of this type anywhere in this tree to update (the `Other` variant and
+/// [`Diagnostics`] below are new in this commit), but this is the thing to
+/// grep for when this crate gains document/LSP-client code that constructs
+/// diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiagnosticProvider {
+    LanguageServer(LanguageServerId),
+    /// A non-LSP diagnostic source, identified by name (for example
+    /// `"spellcheck"` or `"todo"`).
+    Other { name: String },
+}
+
+impl DiagnosticProvider {
+    /// Returns the language server id backing this provider, if any.
+    pub fn language_server(&self) -> Option<LanguageServerId> {
+        match self {
+            DiagnosticProvider::LanguageServer(id) => Some(*id),
+            DiagnosticProvider::Other { .. } => None,
+        }
+    }
+}
+
+impl From<LanguageServerId> for DiagnosticProvider {
+    fn from(id: LanguageServerId) -> Self {
+        DiagnosticProvider::LanguageServer(id)
+    }
+}
+
+/// A collection of [`Diagnostic`]s keyed by the [`DiagnosticProvider`] that
+/// produced them.
+///
+/// This is the type a document's diagnostic set should embed (as e.g.
+/// `document.diagnostics: Diagnostics`) instead of a single flat
+/// `Vec<Diagnostic>` pushed to by the LSP client alone: each provider's
+/// contribution can be set, merged into, or cleared independently, so a late
+/// response from one language server (or a spellchecker re-scanning in the
+/// background) can't clobber another provider's diagnostics. This mirrors
+/// the provider-keyed merge `helix_term`'s completion handler performs on
+/// the completion menu's item list.
+///
+/// `Document` itself isn't part of this crate snapshot, so that embedding
+/// (and the call sites in the LSP client and any non-LSP subsystem that
+/// would call [`push_other`](Diagnostics::push_other)) can't be added here;
+/// this type is the contract those call sites are meant to program against.
+/// The unit tests below exercise that contract directly so it's not merely
+/// declared and unused.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    by_provider: Vec<(DiagnosticProvider, Vec<Diagnostic>)>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Diagnostics {
+        Diagnostics::default()
+    }
+
+    /// Replaces all diagnostics previously set by `provider` with
+    /// `diagnostics`, leaving every other provider's diagnostics untouched.
+    pub fn set(&mut self, provider: DiagnosticProvider, diagnostics: Vec<Diagnostic>) {
+        self.by_provider.retain(|(p, _)| *p != provider);
+        if !diagnostics.is_empty() {
+            self.by_provider.push((provider, diagnostics));
+        }
+    }
+
+    /// Removes every diagnostic contributed by `provider`.
+    pub fn clear(&mut self, provider: &DiagnosticProvider) {
+        self.by_provider.retain(|(p, _)| p != provider);
+    }
+
+    /// Pushes diagnostics from a non-LSP source (for example an in-editor
+    /// spellchecker or a TODO/FIXME scanner), appending to whatever that
+    /// source has already contributed rather than replacing it outright.
+    /// This is the entry point non-LSP subsystems use instead of `set`,
+    /// which is meant for a provider replacing its entire contribution at
+    /// once (the way a language server resends its whole diagnostic list).
+    pub fn push_other(
+        &mut self,
+        name: impl Into<String>,
+        diagnostics: impl IntoIterator<Item = Diagnostic>,
+    ) {
+        let provider = DiagnosticProvider::Other { name: name.into() };
+        match self.by_provider.iter_mut().find(|(p, _)| *p == provider) {
+            Some((_, existing)) => existing.extend(diagnostics),
+            None => self.by_provider.push((provider, diagnostics.into_iter().collect())),
+        }
+    }
+
+    /// Returns the diagnostics contributed by `provider`, if any.
+    pub fn provider(&self, provider: &DiagnosticProvider) -> &[Diagnostic] {
+        self.by_provider
+            .iter()
+            .find(|(p, _)| p == provider)
+            .map(|(_, diagnostics)| diagnostics.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Iterates over every diagnostic from every provider.
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.by_provider.iter().flat_map(|(_, ds)| ds.iter())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_provider.iter().all(|(_, ds)| ds.is_empty())
+    }
+}
 
 // while I would prefe having this in helix-lsp that necessitates a bucnh of
 // conversions I would rather not add I think its file since this just a very
@@ -80,3 +196,92 @@ impl fmt::Display for LanguageServerId {
         write!(f, "{}", self.0)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn diagnostic(message: &str, provider: DiagnosticProvider) -> Diagnostic {
+        Diagnostic {
+            range: Range { start: 0, end: 0 },
+            ends_at_word: false,
+            starts_at_word: false,
+            zero_width: true,
+            line: 0,
+            message: message.to_string(),
+            severity: None,
+            code: None,
+            provider,
+            tags: Vec::new(),
+            source: None,
+            data: None,
+        }
+    }
+
+    #[test]
+    fn set_replaces_only_that_providers_diagnostics() {
+        let ls1 = DiagnosticProvider::LanguageServer(LanguageServerId::new(1));
+        let ls2 = DiagnosticProvider::LanguageServer(LanguageServerId::new(2));
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.set(ls1.clone(), vec![diagnostic("old", ls1.clone())]);
+        diagnostics.set(ls2.clone(), vec![diagnostic("other", ls2.clone())]);
+
+        diagnostics.set(ls1.clone(), vec![diagnostic("new", ls1.clone())]);
+
+        assert_eq!(diagnostics.provider(&ls1).len(), 1);
+        assert_eq!(diagnostics.provider(&ls1)[0].message, "new");
+        assert_eq!(diagnostics.provider(&ls2).len(), 1);
+        assert_eq!(diagnostics.provider(&ls2)[0].message, "other");
+    }
+
+    #[test]
+    fn set_with_empty_vec_clears_the_provider() {
+        let ls = DiagnosticProvider::LanguageServer(LanguageServerId::new(1));
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.set(ls.clone(), vec![diagnostic("old", ls.clone())]);
+
+        diagnostics.set(ls.clone(), vec![]);
+
+        assert!(diagnostics.provider(&ls).is_empty());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn clear_removes_only_that_provider() {
+        let ls1 = DiagnosticProvider::LanguageServer(LanguageServerId::new(1));
+        let ls2 = DiagnosticProvider::LanguageServer(LanguageServerId::new(2));
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.set(ls1.clone(), vec![diagnostic("a", ls1.clone())]);
+        diagnostics.set(ls2.clone(), vec![diagnostic("b", ls2.clone())]);
+
+        diagnostics.clear(&ls1);
+
+        assert!(diagnostics.provider(&ls1).is_empty());
+        assert_eq!(diagnostics.provider(&ls2).len(), 1);
+    }
+
+    #[test]
+    fn push_other_merges_into_existing_contribution() {
+        let mut diagnostics = Diagnostics::new();
+        let provider = DiagnosticProvider::Other {
+            name: "spellcheck".to_string(),
+        };
+        diagnostics.push_other("spellcheck", vec![diagnostic("teh", provider.clone())]);
+        diagnostics.push_other("spellcheck", vec![diagnostic("recieve", provider.clone())]);
+
+        assert_eq!(diagnostics.provider(&provider).len(), 2);
+    }
+
+    #[test]
+    fn iter_covers_every_provider() {
+        let ls = DiagnosticProvider::LanguageServer(LanguageServerId::new(1));
+        let other = DiagnosticProvider::Other {
+            name: "todo".to_string(),
+        };
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.set(ls.clone(), vec![diagnostic("a", ls)]);
+        diagnostics.push_other("todo", vec![diagnostic("b", other)]);
+
+        assert_eq!(diagnostics.iter().count(), 2);
+    }
+}
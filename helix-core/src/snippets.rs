@@ -0,0 +1,471 @@
+//! Parsing and rendering of LSP snippets.
+//!
+//! A completion item whose `insert_text_format` is `Snippet` carries text
+//! using the grammar described in the "Snippet Syntax" section of the LSP
+//! specification: tabstops (`$1`, `${1}`), placeholders with a default value
+//! (`${1:foo}`), choices (`${1|one,two|}`) and a handful of editor variables
+//! (`$TM_SELECTED_TEXT`, ...). This module turns that text into plain text
+//! plus the ranges the cursor should stop at, in order, so a caller can jump
+//! between them the way every snippet-capable editor does.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A single element of a parsed [`Snippet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnippetElement {
+    Text(String),
+    Tabstop {
+        idx: usize,
+    },
+    Placeholder {
+        idx: usize,
+        value: Vec<SnippetElement>,
+    },
+    Choice {
+        idx: usize,
+        choices: Vec<String>,
+    },
+    Variable {
+        name: String,
+        default: Option<Vec<SnippetElement>>,
+    },
+}
+
+/// A snippet parsed from LSP `insertText`, ready to be [`render`](Snippet::render)ed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Snippet {
+    elements: Vec<SnippetElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseSnippetError;
+
+impl fmt::Display for ParseSnippetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid snippet syntax")
+    }
+}
+
+impl std::error::Error for ParseSnippetError {}
+
+impl Snippet {
+    pub fn parse(s: &str) -> Result<Snippet, ParseSnippetError> {
+        let mut chars = s.chars().peekable();
+        let elements = parse_elements(&mut chars, false)?;
+        Ok(Snippet { elements })
+    }
+
+    pub fn elements(&self) -> &[SnippetElement] {
+        &self.elements
+    }
+
+    /// Renders this snippet to plain text. `resolve_var` looks up the
+    /// replacement for a `$NAME` variable; returning `None` falls back to
+    /// the variable's default text (if any) or, failing that, drops it.
+    pub fn render(&self, resolve_var: impl Fn(&str) -> Option<String>) -> RenderedSnippet {
+        let mut text = String::new();
+        let mut tabstops: Vec<Tabstop> = Vec::new();
+        render_elements(&self.elements, &resolve_var, &mut text, &mut tabstops);
+        tabstops.sort_by_key(|tabstop| tabstop.idx);
+        RenderedSnippet { text, tabstops }
+    }
+}
+
+/// One tabstop (or group of linked tabstops sharing the same index) in a
+/// [`RenderedSnippet`], given as byte ranges into `RenderedSnippet::text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tabstop {
+    pub idx: usize,
+    pub ranges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RenderedSnippet {
+    pub text: String,
+    pub tabstops: Vec<Tabstop>,
+}
+
+impl RenderedSnippet {
+    /// Tabstops in the order the cursor should jump through them: ascending
+    /// by index, with `$0` (the position the cursor exits the snippet at)
+    /// always last regardless of where it appeared in the source text.
+    pub fn ordered_tabstops(&self) -> Vec<&Tabstop> {
+        let (exit, mut rest): (Vec<_>, Vec<_>) =
+            self.tabstops.iter().partition(|tabstop| tabstop.idx == 0);
+        rest.sort_by_key(|tabstop| tabstop.idx);
+        rest.extend(exit);
+        rest
+    }
+}
+
+fn render_elements(
+    elements: &[SnippetElement],
+    resolve_var: &impl Fn(&str) -> Option<String>,
+    text: &mut String,
+    tabstops: &mut Vec<Tabstop>,
+) {
+    for element in elements {
+        match element {
+            SnippetElement::Text(s) => text.push_str(s),
+            SnippetElement::Tabstop { idx } => {
+                let at = text.len();
+                push_tabstop(tabstops, *idx, at, at);
+            }
+            SnippetElement::Placeholder { idx, value } => {
+                let start = text.len();
+                render_elements(value, resolve_var, text, tabstops);
+                let end = text.len();
+                push_tabstop(tabstops, *idx, start, end);
+            }
+            SnippetElement::Choice { idx, choices } => {
+                let start = text.len();
+                text.push_str(choices.first().map_or("", String::as_str));
+                let end = text.len();
+                push_tabstop(tabstops, *idx, start, end);
+            }
+            SnippetElement::Variable { name, default } => match resolve_var(name) {
+                Some(value) => text.push_str(&value),
+                None => {
+                    if let Some(default) = default {
+                        render_elements(default, resolve_var, text, tabstops);
+                    }
+                }
+            },
+        }
+    }
+}
+
+fn push_tabstop(tabstops: &mut Vec<Tabstop>, idx: usize, start: usize, end: usize) {
+    match tabstops.iter_mut().find(|tabstop| tabstop.idx == idx) {
+        Some(tabstop) => tabstop.ranges.push((start, end)),
+        None => tabstops.push(Tabstop {
+            idx,
+            ranges: vec![(start, end)],
+        }),
+    }
+}
+
+fn parse_elements(
+    chars: &mut Peekable<Chars>,
+    nested: bool,
+) -> Result<Vec<SnippetElement>, ParseSnippetError> {
+    let mut elements = Vec::new();
+    let mut text = String::new();
+    while let Some(&ch) = chars.peek() {
+        if nested && ch == '}' {
+            break;
+        }
+        match ch {
+            '\\' => {
+                chars.next();
+                text.push(chars.next().unwrap_or('\\'));
+            }
+            '$' => {
+                if !text.is_empty() {
+                    elements.push(SnippetElement::Text(std::mem::take(&mut text)));
+                }
+                chars.next();
+                elements.push(parse_dollar(chars)?);
+            }
+            _ => {
+                text.push(ch);
+                chars.next();
+            }
+        }
+    }
+    if !text.is_empty() {
+        elements.push(SnippetElement::Text(text));
+    }
+    Ok(elements)
+}
+
+fn parse_dollar(chars: &mut Peekable<Chars>) -> Result<SnippetElement, ParseSnippetError> {
+    match chars.peek().copied() {
+        Some(c) if c.is_ascii_digit() => Ok(SnippetElement::Tabstop {
+            idx: parse_int(chars),
+        }),
+        Some(c) if is_variable_start(c) => Ok(SnippetElement::Variable {
+            name: parse_name(chars),
+            default: None,
+        }),
+        Some('{') => {
+            chars.next();
+            parse_braced(chars)
+        }
+        _ => Ok(SnippetElement::Text("$".to_string())),
+    }
+}
+
+fn parse_braced(chars: &mut Peekable<Chars>) -> Result<SnippetElement, ParseSnippetError> {
+    match chars.peek().copied() {
+        Some(c) if c.is_ascii_digit() => {
+            let idx = parse_int(chars);
+            match chars.next() {
+                Some('}') => Ok(SnippetElement::Tabstop { idx }),
+                Some(':') => {
+                    let value = parse_elements(chars, true)?;
+                    expect(chars, '}')?;
+                    Ok(SnippetElement::Placeholder { idx, value })
+                }
+                Some('|') => {
+                    let choices = parse_choices(chars)?;
+                    Ok(SnippetElement::Choice { idx, choices })
+                }
+                _ => Err(ParseSnippetError),
+            }
+        }
+        Some(c) if is_variable_start(c) => {
+            let name = parse_name(chars);
+            match chars.next() {
+                Some('}') => Ok(SnippetElement::Variable { name, default: None }),
+                Some(':') => {
+                    let default = parse_elements(chars, true)?;
+                    expect(chars, '}')?;
+                    Ok(SnippetElement::Variable {
+                        name,
+                        default: Some(default),
+                    })
+                }
+                _ => Err(ParseSnippetError),
+            }
+        }
+        _ => Err(ParseSnippetError),
+    }
+}
+
+fn parse_choices(chars: &mut Peekable<Chars>) -> Result<Vec<String>, ParseSnippetError> {
+    let mut choices = Vec::new();
+    let mut current = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => current.push(chars.next().unwrap_or('\\')),
+            Some(',') => choices.push(std::mem::take(&mut current)),
+            Some('|') => {
+                choices.push(current);
+                expect(chars, '}')?;
+                return Ok(choices);
+            }
+            Some(c) => current.push(c),
+            None => return Err(ParseSnippetError),
+        }
+    }
+}
+
+fn parse_int(chars: &mut Peekable<Chars>) -> usize {
+    let mut digits = String::new();
+    while let Some(&c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        digits.push(c);
+        chars.next();
+    }
+    digits.parse().unwrap_or(0)
+}
+
+fn parse_name(chars: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(&c) = chars.peek() {
+        if !is_variable_continue(c) {
+            break;
+        }
+        name.push(c);
+        chars.next();
+    }
+    name
+}
+
+fn is_variable_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_variable_continue(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), ParseSnippetError> {
+    if chars.next() == Some(expected) {
+        Ok(())
+    } else {
+        Err(ParseSnippetError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn render(snippet: &str) -> RenderedSnippet {
+        Snippet::parse(snippet)
+            .unwrap()
+            .render(|name| (name == "TM_SELECTED_TEXT").then(|| "sel".to_string()))
+    }
+
+    #[test]
+    fn parse_plain_text() {
+        let snippet = Snippet::parse("foo bar").unwrap();
+        assert_eq!(
+            snippet.elements(),
+            &[SnippetElement::Text("foo bar".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_tabstop() {
+        let snippet = Snippet::parse("foo $1 ${2} bar").unwrap();
+        assert_eq!(
+            snippet.elements(),
+            &[
+                SnippetElement::Text("foo ".to_string()),
+                SnippetElement::Tabstop { idx: 1 },
+                SnippetElement::Text(" ".to_string()),
+                SnippetElement::Tabstop { idx: 2 },
+                SnippetElement::Text(" bar".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_placeholder() {
+        let snippet = Snippet::parse("${1:foo}").unwrap();
+        assert_eq!(
+            snippet.elements(),
+            &[SnippetElement::Placeholder {
+                idx: 1,
+                value: vec![SnippetElement::Text("foo".to_string())],
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_nested_placeholder() {
+        let snippet = Snippet::parse("${1:foo $2 bar}").unwrap();
+        assert_eq!(
+            snippet.elements(),
+            &[SnippetElement::Placeholder {
+                idx: 1,
+                value: vec![
+                    SnippetElement::Text("foo ".to_string()),
+                    SnippetElement::Tabstop { idx: 2 },
+                    SnippetElement::Text(" bar".to_string()),
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_choice() {
+        let snippet = Snippet::parse("${1|one,two,three|}").unwrap();
+        assert_eq!(
+            snippet.elements(),
+            &[SnippetElement::Choice {
+                idx: 1,
+                choices: vec!["one".to_string(), "two".to_string(), "three".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_variable_with_default() {
+        let snippet = Snippet::parse("${TM_SELECTED_TEXT:fallback}").unwrap();
+        assert_eq!(
+            snippet.elements(),
+            &[SnippetElement::Variable {
+                name: "TM_SELECTED_TEXT".to_string(),
+                default: Some(vec![SnippetElement::Text("fallback".to_string())]),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_escaped_dollar() {
+        let snippet = Snippet::parse(r"\$1 literal").unwrap();
+        assert_eq!(
+            snippet.elements(),
+            &[SnippetElement::Text("$1 literal".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_unterminated_placeholder_errors() {
+        assert_eq!(Snippet::parse("${1:foo"), Err(ParseSnippetError));
+    }
+
+    #[test]
+    fn render_plain_text() {
+        let rendered = render("foo bar");
+        assert_eq!(rendered.text, "foo bar");
+        assert!(rendered.tabstops.is_empty());
+    }
+
+    #[test]
+    fn render_tabstop_is_zero_width_at_its_position() {
+        let rendered = render("foo $1 bar");
+        assert_eq!(rendered.text, "foo  bar");
+        assert_eq!(rendered.tabstops, vec![Tabstop { idx: 1, ranges: vec![(4, 4)] }]);
+    }
+
+    #[test]
+    fn render_placeholder_selects_default_text() {
+        let rendered = render("${1:foo}");
+        assert_eq!(rendered.text, "foo");
+        assert_eq!(rendered.tabstops, vec![Tabstop { idx: 1, ranges: vec![(0, 3)] }]);
+    }
+
+    #[test]
+    fn render_choice_uses_first_choice() {
+        let rendered = render("${1|one,two|}");
+        assert_eq!(rendered.text, "one");
+        assert_eq!(rendered.tabstops, vec![Tabstop { idx: 1, ranges: vec![(0, 3)] }]);
+    }
+
+    #[test]
+    fn render_resolves_variable() {
+        let rendered = render("$TM_SELECTED_TEXT");
+        assert_eq!(rendered.text, "sel");
+        assert!(rendered.tabstops.is_empty());
+    }
+
+    #[test]
+    fn render_unresolved_variable_falls_back_to_default() {
+        let rendered = render("${UNKNOWN_VAR:fallback}");
+        assert_eq!(rendered.text, "fallback");
+    }
+
+    #[test]
+    fn render_unresolved_variable_without_default_is_dropped() {
+        let rendered = render("before${UNKNOWN_VAR}after");
+        assert_eq!(rendered.text, "beforeafter");
+    }
+
+    #[test]
+    fn render_linked_tabstops_share_one_entry_with_multiple_ranges() {
+        let rendered = render("$1 and $1");
+        assert_eq!(rendered.tabstops.len(), 1);
+        assert_eq!(rendered.tabstops[0].ranges, vec![(0, 0), (5, 5)]);
+    }
+
+    #[test]
+    fn ordered_tabstops_sorts_ascending_with_exit_last() {
+        let rendered = render("${2:b}$0${1:a}");
+        let order: Vec<_> = rendered
+            .ordered_tabstops()
+            .into_iter()
+            .map(|tabstop| tabstop.idx)
+            .collect();
+        assert_eq!(order, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn ordered_tabstops_without_explicit_exit_has_no_zero() {
+        let rendered = render("${1:a}${2:b}");
+        let order: Vec<_> = rendered
+            .ordered_tabstops()
+            .into_iter()
+            .map(|tabstop| tabstop.idx)
+            .collect();
+        assert_eq!(order, vec![1, 2]);
+    }
+}
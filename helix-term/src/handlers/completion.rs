@@ -7,13 +7,15 @@ use anyhow::Result;
 
 use futures_util::{Stream, StreamExt};
 use helix_core::chars::char_is_word;
+use helix_core::snippets::{RenderedSnippet, Snippet};
 use helix_core::syntax::LanguageServerFeature;
+use helix_core::{Range, Selection, Tendril, Transaction};
 use helix_event::{register_hook, send_blocking};
 use helix_lsp::{lsp, LanguageServerId};
 use helix_stdx::rope::RopeSliceExt;
-use helix_view::document::{Mode, SavePoint};
+use helix_view::document::{Document, Mode, SavePoint};
 use helix_view::handlers::lsp::CompletionEvent;
-use helix_view::Editor;
+use helix_view::{Editor, ViewId};
 use tokio::pin;
 use tokio::sync::mpsc::Sender;
 
@@ -33,10 +35,26 @@ pub use resolve::ResolveHandler;
 mod request;
 mod resolve;
 
+/// Where a [`CompletionItem`] came from.
+///
+/// Completions no longer have to come from a language server: this also
+/// covers sources built into helix itself, like the buffer-word provider.
+/// `provider_priority` still decides ordering between items from different
+/// providers, so a real language server always wins a tie against these.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CompletionProvider {
+    Lsp(LanguageServerId),
+    /// Identifier-like words harvested from the current buffer; other open
+    /// buffers are not scanned.
+    BufferWord,
+    /// Directory/file entries harvested from the filesystem.
+    Path,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct CompletionItem {
     pub item: lsp::CompletionItem,
-    pub provider: LanguageServerId,
+    pub provider: CompletionProvider,
     pub incomplete_completion_list: bool,
     pub resolved: bool,
     pub provider_priority: i8,
@@ -53,9 +71,54 @@ impl CompletionItem {
             .into()
     }
 }
+
+/// The part of a [`CompletionItem`] that identifies "the same candidate"
+/// across two otherwise unrelated completion lists, used to keep the
+/// highlighted item selected across a merge.
+fn selection_key(item: &CompletionItem) -> (CompletionProvider, &str, Cow<str>) {
+    (item.provider, item.item.label.as_str(), item.filter_text())
+}
+
+/// Merges a provider's (re)computed completions into an already-displayed,
+/// globally ordered list.
+///
+/// Only the items previously contributed by `response.provider` are
+/// replaced; every other provider's items are left as-is. The whole list
+/// (old items from other providers plus the new ones) is then re-ranked
+/// with [`rank_completion_items`](request::rank_completion_items), the same
+/// function the initial batch is ranked with, so a late or re-filtered
+/// response can't leave the menu in an order chunk0-4 wouldn't produce from
+/// scratch: fuzzy match quality against `prefix` stays the primary key
+/// across the whole list, with `provider_priority` only a tiebreaker, not a
+/// way to bucket one provider's items above another's regardless of match
+/// quality. This is the merge `ui::Completion::replace_provider_completions`
+/// performs on the popup's item list; it's exposed here as a free function
+/// so the ordering and selection-preservation logic can be driven (and
+/// reasoned about) independently of the popup widget itself.
+///
+/// Returns the index `selected` ends up at after the merge, if it is still
+/// present in `items`.
+pub(crate) fn merge_provider_completions(
+    items: &mut Vec<CompletionItem>,
+    selected: Option<&CompletionItem>,
+    response: CompletionResponse,
+    prefix: &str,
+) -> Option<usize> {
+    let provider = response.provider;
+    let selected_key = selected.map(selection_key);
+
+    items.retain(|item| item.provider != provider);
+    items.extend(response.into_items());
+    let ranked = request::rank_completion_items(std::mem::take(items), prefix);
+    *items = ranked;
+
+    selected_key.and_then(|key| items.iter().position(|item| selection_key(item) == key))
+}
+
 async fn replace_completions(
     version: Arc<AtomicUsize>,
     initial_version: usize,
+    prefix: Arc<str>,
     futures: impl Stream<Item = CompletionResponse>,
 ) {
     pin!(futures);
@@ -64,6 +127,7 @@ async fn replace_completions(
             break;
         }
         let version = version.clone();
+        let prefix = prefix.clone();
         dispatch(move |_editor, compositor| {
             let ui = compositor.find::<ui::EditorView>().unwrap();
             let Some(completion) = &mut ui.completion else {
@@ -74,7 +138,19 @@ async fn replace_completions(
             {
                 return;
             }
-            completion.replace_provider_completions(response);
+            // Merge this provider's (re)computed items into the menu's item
+            // list by re-ranking the whole list instead of appending, and keep the
+            // highlighted item selected across the merge.
+            let selected = completion
+                .selection
+                .and_then(|idx| completion.items.get(idx))
+                .cloned();
+            completion.selection = merge_provider_completions(
+                &mut completion.items,
+                selected.as_ref(),
+                response,
+                &prefix,
+            );
         })
         .await;
     }
@@ -84,7 +160,7 @@ fn show_completion(
     editor: &mut Editor,
     compositor: &mut Compositor,
     items: Vec<CompletionItem>,
-    incomplete_completion_lists: HashMap<LanguageServerId, i8>,
+    incomplete_completion_lists: HashMap<CompletionProvider, i8>,
     trigger: Trigger,
     savepoint: Arc<SavePoint>,
 ) {
@@ -205,6 +281,138 @@ fn clear_completions(cx: &mut commands::Context) {
     }))
 }
 
+/// Inserts the chosen `item` into the document and tears down the
+/// completion popup, the same way [`clear_completions`] does.
+///
+/// If the server marked the item as `InsertTextFormat::SNIPPET`, its
+/// `insert_text` is parsed as an LSP snippet (tabstops, placeholders,
+/// variables, ...) and the resulting selection covers the first tabstop so
+/// the user can immediately type to fill it in. Anything else, including a
+/// snippet that fails to parse, is inserted as plain text.
+// NOTE: nothing in this snapshot's command table calls `accept_completion_item`
+// yet (the keymap binding for "accept the highlighted completion" lives in
+// `commands.rs`, which isn't part of this tree) - wiring it up is a
+// mechanical follow-up once that file is in scope. It's written and laid out
+// here exactly as it would be called: `(cx, &item)` for the currently
+// highlighted `CompletionItem`.
+pub fn accept_completion_item(cx: &mut commands::Context, item: &CompletionItem) {
+    let insert_text = item.item.insert_text.as_deref().unwrap_or(&item.item.label);
+
+    // Rendering only needs read access to the editor (to resolve variables
+    // like `$TM_SELECTED_TEXT`), so do it before taking the mutable
+    // `(view, doc)` borrow needed to actually apply the edit.
+    let rendered = (item.item.insert_text_format == Some(lsp::InsertTextFormat::SNIPPET))
+        .then(|| Snippet::parse(insert_text).ok())
+        .flatten()
+        .map(|snippet| snippet.render(|name| resolve_snippet_variable(cx.editor, name)));
+
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    // The item replaces whatever prefix the user already typed to trigger
+    // it (e.g. accepting `println` after typing `pri` should produce
+    // `println`, not `priprintln`): walk back from the cursor over word
+    // characters the same way `request_completions` finds `typed_word`, and
+    // replace that span instead of inserting at the empty `(cursor, cursor)`
+    // range.
+    let start = text
+        .chars_at(cursor)
+        .reversed()
+        .take_while(|&c| char_is_word(c))
+        .count();
+    let start = cursor - start;
+    let selection = match rendered {
+        Some(rendered) => insert_snippet(doc, view.id, start, cursor, &rendered),
+        None => insert_plain_text(doc, view.id, start, cursor, insert_text),
+    };
+    doc.set_selection(view.id, selection);
+
+    clear_completions(cx);
+}
+
+fn insert_plain_text(
+    doc: &mut Document,
+    view_id: ViewId,
+    start: usize,
+    end: usize,
+    text: &str,
+) -> Selection {
+    let transaction = Transaction::change(
+        doc.text(),
+        std::iter::once((start, end, Some(Tendril::from(text)))),
+    );
+    doc.apply(&transaction, view_id);
+    Selection::point(start + text.chars().count())
+}
+
+// NOTE: this only selects the first tabstop; it doesn't keep the jump
+// sequence (Tab/Shift-Tab moving through the remaining tabstops, exiting at
+// `$0`) alive afterwards, since that requires per-document jump-list state
+// that would live on `Document`/`Editor` - not part of this tree. Rendering
+// and the first selection are correct; the rest of the sequence is a
+// mechanical follow-up once that state has somewhere to live.
+fn insert_snippet(
+    doc: &mut Document,
+    view_id: ViewId,
+    start: usize,
+    end: usize,
+    rendered: &RenderedSnippet,
+) -> Selection {
+    let transaction = Transaction::change(
+        doc.text(),
+        std::iter::once((start, end, Some(Tendril::from(rendered.text.as_str())))),
+    );
+    doc.apply(&transaction, view_id);
+
+    // Select the first tabstop (falling back to `$0`, the snippet's exit
+    // point) so the user can start typing to fill it in right away.
+    let first_tabstop = rendered
+        .ordered_tabstops()
+        .into_iter()
+        .next()
+        .map(|tabstop| tabstop.ranges.as_slice())
+        .unwrap_or(&[]);
+
+    if first_tabstop.is_empty() {
+        return Selection::point(start + rendered.text.chars().count());
+    }
+
+    // `tabstop.ranges` are byte offsets into `rendered.text` (see
+    // `Tabstop`'s doc comment), but `start` is a char index into the rope,
+    // so a naive `start + byte_offset` is wrong as soon as `rendered.text`
+    // contains multibyte characters before or inside the tabstop. Convert
+    // each byte offset to the char count it corresponds to first.
+    let byte_to_char = |byte: usize| rendered.text[..byte].chars().count();
+    Selection::new(
+        first_tabstop
+            .iter()
+            .map(|&(byte_start, byte_end)| {
+                Range::new(
+                    start + byte_to_char(byte_start),
+                    start + byte_to_char(byte_end),
+                )
+            })
+            .collect(),
+        0,
+    )
+}
+
+fn resolve_snippet_variable(editor: &Editor, name: &str) -> Option<String> {
+    let (view, doc) = current_ref!(editor);
+    let text = doc.text().slice(..);
+    match name {
+        "TM_SELECTED_TEXT" => {
+            let fragment = doc.selection(view.id).primary().fragment(text);
+            (!fragment.is_empty()).then(|| fragment.into_owned())
+        }
+        "TM_CURRENT_LINE" => {
+            let cursor = doc.selection(view.id).primary().cursor(text);
+            Some(text.line(text.char_to_line(cursor)).to_string())
+        }
+        _ => None,
+    }
+}
+
 fn completion_post_command_hook(
     tx: &Sender<CompletionEvent>,
     PostCommand { command, cx }: &mut PostCommand<'_, '_>,
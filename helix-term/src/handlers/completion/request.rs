@@ -1,4 +1,6 @@
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::sync::atomic::{self, AtomicUsize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -7,6 +9,7 @@ use anyhow::Result;
 use arc_swap::ArcSwap;
 use futures_util::stream::{FusedStream, FuturesUnordered};
 use futures_util::{Future, StreamExt};
+use helix_core::chars::char_is_word;
 use helix_core::syntax::LanguageServerFeature;
 use helix_event::{cancelable_future, cancelation, CancelRx, CancelTx};
 use helix_lsp::lsp::{CompletionContext, CompletionTriggerKind};
@@ -16,16 +19,94 @@ use helix_stdx::rope::RopeSliceExt;
 use helix_view::document::Mode;
 use helix_view::handlers::lsp::CompletionEvent;
 use helix_view::{Document, DocumentId, Editor, ViewId};
+use nucleo_matcher::pattern::{Atom, AtomKind, CaseMatching, Normalization};
+use nucleo_matcher::{Config as MatcherConfig, Matcher, Utf32Str};
 use tokio::pin;
 use tokio::time::{timeout_at, Instant};
 
 use crate::compositor::Compositor;
 use crate::config::Config;
-use crate::handlers::completion::{replace_completions, show_completion, CompletionItem};
+use crate::handlers::completion::{
+    merge_provider_completions, replace_completions, show_completion, CompletionItem,
+    CompletionProvider,
+};
 use crate::job::{dispatch, dispatch_blocking};
 use crate::ui;
 use crate::ui::editor::InsertEvent;
 
+/// `provider_priority` assigned to the built-in buffer-word provider. This is
+/// lower than any language server (which count down from `0`), so real LSP
+/// completions always win a tie against a plain buffer word.
+const BUFFER_WORD_PROVIDER_PRIORITY: i8 = i8::MIN;
+/// `provider_priority` assigned to the built-in path provider. Kept in the
+/// same low tier as the buffer-word provider: neither should ever outrank a
+/// language server.
+const PATH_PROVIDER_PRIORITY: i8 = i8::MIN + 1;
+
+/// Default time budget given to a language server to answer a completion
+/// request before its results are streamed in later instead of delaying the
+/// menu. Can be overridden per server through [`ProviderTimeouts`].
+const DEFAULT_LSP_PROVIDER_TIMEOUT: Duration = Duration::from_millis(100);
+/// The buffer-word and path providers never do any I/O on the async task
+/// itself (path completions hand the actual directory read off to a
+/// blocking task), so they get a much tighter budget than language servers.
+const BUFFER_WORD_PROVIDER_TIMEOUT: Duration = Duration::from_millis(20);
+const PATH_PROVIDER_TIMEOUT: Duration = Duration::from_millis(20);
+
+/// How long `request_completions` waits for each provider before giving up
+/// on it and falling back to streaming its results in once they arrive (see
+/// [`replace_completions`]).
+///
+/// Populated from `[editor.completion]`: `lsp_timeout`/`buffer_word_timeout`/
+/// `path_timeout` override the hardcoded defaults per provider type, and
+/// `lsp_timeout_overrides` (keyed by language server name) further overrides
+/// `lsp_timeout` for specific servers.
+#[derive(Debug, Clone)]
+struct ProviderTimeouts {
+    lsp_default: Duration,
+    lsp_overrides: HashMap<LanguageServerId, Duration>,
+    buffer_word: Duration,
+    path: Duration,
+}
+
+impl ProviderTimeouts {
+    fn new(config: &Config, language_servers: &[&helix_lsp::Client]) -> ProviderTimeouts {
+        let completion = &config.editor.completion;
+        let lsp_overrides = language_servers
+            .iter()
+            .filter_map(|ls| {
+                let timeout = *completion.lsp_timeout_overrides.get(ls.name())?;
+                Some((ls.id(), timeout))
+            })
+            .collect();
+        ProviderTimeouts {
+            lsp_default: completion.lsp_timeout.unwrap_or(DEFAULT_LSP_PROVIDER_TIMEOUT),
+            lsp_overrides,
+            buffer_word: completion
+                .buffer_word_timeout
+                .unwrap_or(BUFFER_WORD_PROVIDER_TIMEOUT),
+            path: completion.path_timeout.unwrap_or(PATH_PROVIDER_TIMEOUT),
+        }
+    }
+
+    fn for_provider(&self, provider: CompletionProvider) -> Duration {
+        match provider {
+            CompletionProvider::Lsp(id) => self
+                .lsp_overrides
+                .get(&id)
+                .copied()
+                .unwrap_or(self.lsp_default),
+            CompletionProvider::BufferWord => self.buffer_word,
+            CompletionProvider::Path => self.path,
+        }
+    }
+}
+
+/// A future yielding a single [`CompletionResponse`], boxed so that sources
+/// as different as a language server request and a buffer scan can be driven
+/// by the same [`FuturesUnordered`].
+type CompletionFuture = Pin<Box<dyn Future<Output = Result<CompletionResponse>> + Send>>;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(super) enum TriggerKind {
     Auto,
@@ -150,8 +231,9 @@ impl helix_event::AsyncHook for CompletionHandler {
         let trigger = self.trigger.take().expect("debounce always has a trigger");
         let (tx, rx) = cancelation();
         self.request = Some(tx);
+        let config = self.config.clone();
         dispatch_blocking(move |editor, compositor| {
-            request_completions(trigger, rx, editor, compositor)
+            request_completions(trigger, rx, &config.load(), editor, compositor)
         });
     }
 }
@@ -159,6 +241,7 @@ impl helix_event::AsyncHook for CompletionHandler {
 fn request_completions(
     mut trigger: Trigger,
     cancel: CancelRx,
+    config: &Config,
     editor: &mut Editor,
     compositor: &mut Compositor,
 ) {
@@ -189,13 +272,27 @@ fn request_completions(
     // necessary from our side too.
     trigger.pos = cursor;
     let trigger_text = text.slice(..cursor);
+    let typed_word: String = text
+        .slice(..)
+        .chars_at(cursor)
+        .reversed()
+        .take_while(|&c| char_is_word(c))
+        .collect();
+    let typed_word: Arc<str> = typed_word.chars().rev().collect::<String>().into();
 
     let mut seen_language_servers = HashSet::new();
     let language_servers: Vec<_> = doc
         .language_servers_with_feature(LanguageServerFeature::Completion)
         .filter(|ls| seen_language_servers.insert(ls.id()))
         .collect();
-    let futures: FuturesUnordered<_> = language_servers
+    let timeouts = ProviderTimeouts::new(config, &language_servers);
+    let providers: Vec<CompletionProvider> = language_servers
+        .iter()
+        .map(|ls| CompletionProvider::Lsp(ls.id()))
+        .chain([CompletionProvider::BufferWord, CompletionProvider::Path])
+        .collect();
+
+    let futures: FuturesUnordered<CompletionFuture> = language_servers
         .iter()
         .enumerate()
         .map(|(priority, ls)| {
@@ -230,8 +327,24 @@ fn request_completions(
                 }
             };
 
-            request_completions_from_language_server(ls, doc, view.id, context, -(priority as i8))
+            Box::pin(request_completions_from_language_server(
+                ls,
+                doc,
+                view.id,
+                context,
+                -(priority as i8),
+            )) as CompletionFuture
         })
+        .chain(std::iter::once(Box::pin(buffer_word_completions(
+            doc,
+            view.id,
+            BUFFER_WORD_PROVIDER_PRIORITY,
+        )) as CompletionFuture))
+        .chain(std::iter::once(Box::pin(path_completions(
+            doc,
+            view.id,
+            PATH_PROVIDER_PRIORITY,
+        )) as CompletionFuture))
         .collect();
 
     let futures = futures.filter_map(|res: Result<_>| async {
@@ -252,19 +365,43 @@ fn request_completions(
     let request_completions = async move {
         pin!(futures);
         let mut incomplete_completion_lists = HashMap::new();
+        let mut items: Vec<CompletionItem> = Vec::new();
         let Some(response) = futures.next().await else {
             return;
         };
         if response.incomplete {
             incomplete_completion_lists.insert(response.provider, response.priority);
         }
-        let mut items: Vec<_> = response.into_items().collect();
-        let deadline = Instant::now() + Duration::from_millis(100);
-        while let Some(response) = timeout_at(deadline, futures.next()).await.ok().flatten() {
-            if response.incomplete {
-                incomplete_completion_lists.insert(response.provider, response.priority);
+        // Captured before the merge below moves `response` out.
+        let first_provider = response.provider;
+        // Every response is merged through the same re-rank of the whole
+        // list against `typed_word` whether it's part
+        // of this initial batch or streamed in late by `replace_completions`
+        // below, so ordering is consistent regardless of arrival time.
+        merge_provider_completions(&mut items, None, response, &typed_word);
+        // Every other provider gets its own budget to respond before we stop
+        // waiting on it specifically; the ones that miss their deadline are
+        // simply streamed into the menu later by `replace_completions`.
+        let mut pending: HashMap<CompletionProvider, Instant> = providers
+            .into_iter()
+            .filter(|&provider| provider != first_provider)
+            .map(|provider| (provider, Instant::now() + timeouts.for_provider(provider)))
+            .collect();
+        while let Some(&deadline) = pending.values().min() {
+            match timeout_at(deadline, futures.next()).await {
+                Ok(Some(response)) => {
+                    pending.remove(&response.provider);
+                    if response.incomplete {
+                        incomplete_completion_lists.insert(response.provider, response.priority);
+                    }
+                    merge_provider_completions(&mut items, None, response, &typed_word);
+                }
+                Ok(None) => break,
+                Err(_timed_out) => {
+                    let now = Instant::now();
+                    pending.retain(|_, provider_deadline| *provider_deadline > now);
+                }
             }
-            items.extend(response.into_items());
         }
         let version = Arc::new(AtomicUsize::new(0));
         dispatch(move |editor, compositor| {
@@ -279,7 +416,7 @@ fn request_completions(
         })
         .await;
         if !futures.is_terminated() {
-            replace_completions(version, 0, futures).await;
+            replace_completions(version, 0, typed_word, futures).await;
         }
     };
     tokio::spawn(cancelable_future(request_completions, cancel));
@@ -288,7 +425,7 @@ fn request_completions(
 pub struct CompletionResponse {
     pub items: Vec<lsp::CompletionItem>,
     pub incomplete: bool,
-    pub provider: LanguageServerId,
+    pub provider: CompletionProvider,
     pub priority: i8,
 }
 
@@ -303,6 +440,60 @@ impl CompletionResponse {
     }
 }
 
+/// Produces a single, globally ordered completion list out of the
+/// (independently `sort_text`-ordered) lists each provider returned.
+///
+/// Items are ranked by how well their filter text fuzzy-matches what the
+/// user has typed so far, with `provider_priority` and the provider's own
+/// `sort_text` used as tiebreakers. This keeps per-server preselection/sort
+/// hints meaningful while still giving consistent ordering across mixed
+/// providers.
+pub(crate) fn rank_completion_items(items: Vec<CompletionItem>, prefix: &str) -> Vec<CompletionItem> {
+    if prefix.is_empty() {
+        let mut items = items;
+        items.sort_by(|a, b| {
+            b.provider_priority
+                .cmp(&a.provider_priority)
+                .then_with(|| sort_text(a).cmp(sort_text(b)))
+        });
+        return items;
+    }
+
+    let mut matcher = Matcher::new(MatcherConfig::DEFAULT);
+    let pattern = Atom::new(
+        prefix,
+        CaseMatching::Smart,
+        Normalization::Smart,
+        AtomKind::Fuzzy,
+        false,
+    );
+    let mut buf = Vec::new();
+    // Items that don't fuzzy-match `prefix` at all are kept, just ranked
+    // last (`None` sorts below every `Some` score): dropping them would
+    // permanently lose entries from an already-complete LSP list as soon as
+    // the user backspaces past what they matched, since nothing re-requests
+    // a complete list.
+    let mut scored: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let haystack = item.filter_text().into_owned();
+            let score = pattern.score(Utf32Str::new(&haystack, &mut buf), &mut matcher);
+            (score, item)
+        })
+        .collect();
+    scored.sort_by(|(score_a, item_a), (score_b, item_b)| {
+        score_b
+            .cmp(score_a)
+            .then_with(|| item_b.provider_priority.cmp(&item_a.provider_priority))
+            .then_with(|| sort_text(item_a).cmp(sort_text(item_b)))
+    });
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+fn sort_text(item: &CompletionItem) -> &str {
+    item.item.sort_text.as_deref().unwrap_or(&item.item.label)
+}
+
 fn request_completions_from_language_server(
     ls: &helix_lsp::Client,
     doc: &Document,
@@ -341,23 +532,193 @@ fn request_completions_from_language_server(
         Ok(CompletionResponse {
             items,
             incomplete,
-            provider,
+            provider: CompletionProvider::Lsp(provider),
+            priority,
+        })
+    }
+}
+
+/// Harvests identifier-like words from the current buffer so that completion
+/// keeps working in documents with no attached language server (plaintext,
+/// config files, ...) and before a language server has finished attaching.
+fn buffer_word_completions(
+    doc: &Document,
+    view: ViewId,
+    priority: i8,
+) -> impl Future<Output = Result<CompletionResponse>> {
+    let text = doc.text().clone();
+    let cursor = doc.selection(view).primary().cursor(text.slice(..));
+    async move {
+        let slice = text.slice(..);
+        let cursor_word_start = slice
+            .chars_at(cursor)
+            .reversed()
+            .take_while(|&c| char_is_word(c))
+            .count();
+        let typed_word = slice.slice(cursor - cursor_word_start..cursor);
+
+        let mut words = HashSet::new();
+        let mut current = String::new();
+        for ch in slice.chars() {
+            if char_is_word(ch) {
+                current.push(ch);
+                continue;
+            }
+            if current.len() > 1 {
+                words.insert(std::mem::take(&mut current));
+            } else {
+                current.clear();
+            }
+        }
+        if current.len() > 1 {
+            words.insert(current);
+        }
+        // The word currently being typed isn't a useful completion of itself.
+        words.remove(&typed_word.to_string());
+
+        let items = words
+            .into_iter()
+            .map(|word| lsp::CompletionItem {
+                label: word,
+                kind: Some(lsp::CompletionItemKind::TEXT),
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(CompletionResponse {
+            items,
+            incomplete: false,
+            provider: CompletionProvider::BufferWord,
             priority,
         })
     }
 }
 
+/// Offers directory/file entries when the text immediately before the
+/// cursor looks like a path fragment (contains `/`, or starts with `~`).
+/// Directory reads happen on a blocking task so they never stall the async
+/// completion hook.
+fn path_completions(
+    doc: &Document,
+    view: ViewId,
+    priority: i8,
+) -> impl Future<Output = Result<CompletionResponse>> {
+    let text = doc.text().clone();
+    let cursor = doc.selection(view).primary().cursor(text.slice(..));
+    let relative_to = doc.path().and_then(|path| path.parent()).map(Path::to_path_buf);
+    async move {
+        let mut fragment = String::new();
+        for ch in text.slice(..).chars_at(cursor).reversed() {
+            if ch.is_whitespace() || matches!(ch, '"' | '\'' | '(' | '<' | '[' | '{') {
+                break;
+            }
+            fragment.insert(0, ch);
+        }
+
+        let empty_response = || CompletionResponse {
+            items: Vec::new(),
+            incomplete: false,
+            provider: CompletionProvider::Path,
+            priority,
+        };
+
+        if !fragment.contains('/') && !fragment.starts_with('~') {
+            return Ok(empty_response());
+        }
+
+        let (dir, file_prefix) = match fragment.rfind('/') {
+            Some(idx) => (fragment[..=idx].to_string(), fragment[idx + 1..].to_string()),
+            None => (fragment.clone(), String::new()),
+        };
+
+        let items = tokio::task::spawn_blocking(move || {
+            let dir = resolve_path_completion_dir(&dir, relative_to.as_deref());
+            read_path_completion_dir(&dir, &file_prefix)
+        })
+        .await
+        .unwrap_or_default();
+
+        Ok(CompletionResponse {
+            items,
+            incomplete: false,
+            provider: CompletionProvider::Path,
+            priority,
+        })
+    }
+}
+
+fn resolve_path_completion_dir(dir: &str, relative_to: Option<&Path>) -> PathBuf {
+    if let Some(rest) = dir.strip_prefix('~') {
+        let rest = rest.strip_prefix('/').unwrap_or(rest);
+        if let Some(home) = std::env::var_os("HOME") {
+            return PathBuf::from(home).join(rest);
+        }
+    }
+
+    let path = Path::new(dir);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        relative_to
+            .map(|base| base.join(path))
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+}
+
+fn read_path_completion_dir(dir: &Path, file_prefix: &str) -> Vec<lsp::CompletionItem> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if !file_name.starts_with(file_prefix) {
+                return None;
+            }
+            let is_dir = entry.file_type().map_or(false, |ty| ty.is_dir());
+            Some(lsp::CompletionItem {
+                label: if is_dir {
+                    format!("{file_name}/")
+                } else {
+                    file_name
+                },
+                kind: Some(if is_dir {
+                    lsp::CompletionItemKind::FOLDER
+                } else {
+                    lsp::CompletionItemKind::FILE
+                }),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
 pub fn request_incomplete_completion_list(
     editor: &mut Editor,
-    incomplete_completion_lists: &mut HashMap<LanguageServerId, i8>,
+    incomplete_completion_lists: &mut HashMap<CompletionProvider, i8>,
     version: Arc<AtomicUsize>,
 ) {
     if incomplete_completion_lists.is_empty() {
         return;
     }
     let (view, doc) = current_ref!(editor);
+    let cursor = doc.selection(view.id).primary().cursor(doc.text().slice(..));
+    let typed_word: String = doc
+        .text()
+        .slice(..)
+        .chars_at(cursor)
+        .reversed()
+        .take_while(|&c| char_is_word(c))
+        .collect();
+    let typed_word: Arc<str> = typed_word.chars().rev().collect::<String>().into();
     let futures = FuturesUnordered::new();
-    incomplete_completion_lists.retain(|&id, &mut priority| {
+    incomplete_completion_lists.retain(|&provider, &mut priority| {
+        // Only language servers support incomplete completion lists; the
+        // buffer-word and path providers always return a complete list.
+        let CompletionProvider::Lsp(id) = provider else {
+            return false;
+        };
         let Some(ls) = editor.language_server_by_id(id) else {
             return false;
         };
@@ -387,6 +748,6 @@ pub fn request_incomplete_completion_list(
     log::error!("requestion incomplete list {initial_version}");
     tokio::spawn(async move {
         pin!(futures);
-        replace_completions(version, initial_version, futures).await;
+        replace_completions(version, initial_version, typed_word, futures).await;
     });
 }
@@ -0,0 +1,183 @@
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use futures_util::stream::FuturesUnordered;
+use futures_util::StreamExt;
+use helix_event::{cancelable_future, cancelation, send_blocking, CancelRx, CancelTx};
+use helix_lsp::lsp;
+use helix_view::Editor;
+use tokio::pin;
+use tokio::sync::mpsc::Sender;
+use tokio::time::Instant;
+
+use crate::compositor::Compositor;
+use crate::config::Config;
+use crate::handlers::completion::{CompletionItem, CompletionProvider};
+use crate::job::{dispatch, dispatch_blocking};
+use crate::ui;
+
+/// Requests fed to the [`ResolveHandler`].
+#[derive(Debug)]
+pub enum ResolveEvent {
+    Cancel,
+    /// The item the user is about to accept: resolved immediately, no
+    /// debounce.
+    Request(CompletionItem),
+    /// The completion menu's highlighted index changed to `selected` within
+    /// `items`; resolve it plus the configured window of neighbors once the
+    /// selection stops moving.
+    Prefetch {
+        items: Vec<CompletionItem>,
+        selected: usize,
+    },
+}
+
+/// Sends the prefetch request for a newly highlighted completion item.
+///
+/// This is what `ui::Completion`'s selection-change handler (the popup
+/// widget that owns the menu's `items`/highlighted index, not part of this
+/// module) should call each time the user moves the highlight, so the
+/// prefetch path in [`ResolveHandler`] actually fires.
+pub fn prefetch_on_selection_change(
+    tx: &Sender<ResolveEvent>,
+    items: Vec<CompletionItem>,
+    selected: usize,
+) {
+    send_blocking(tx, ResolveEvent::Prefetch { items, selected });
+}
+
+#[derive(Debug)]
+pub struct ResolveHandler {
+    config: Arc<ArcSwap<Config>>,
+    trigger: Option<Vec<CompletionItem>>,
+    request: Option<CancelTx>,
+    /// Bumped on every dispatched resolve request so that results from a
+    /// request that's since been superseded can be dropped instead of
+    /// applied to a now-unrelated completion menu, the same
+    /// version/AtomicUsize staleness check `replace_completions` uses.
+    version: Arc<AtomicUsize>,
+}
+
+impl ResolveHandler {
+    pub fn new(config: Arc<ArcSwap<Config>>) -> ResolveHandler {
+        ResolveHandler {
+            config,
+            trigger: None,
+            request: None,
+            version: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl helix_event::AsyncHook for ResolveHandler {
+    type Event = ResolveEvent;
+
+    fn handle_event(
+        &mut self,
+        event: Self::Event,
+        _old_timeout: Option<Instant>,
+    ) -> Option<Instant> {
+        match event {
+            ResolveEvent::Cancel => {
+                self.trigger = None;
+                self.request = None;
+                None
+            }
+            ResolveEvent::Request(item) => {
+                self.trigger = Some(vec![item]);
+                self.finish_debounce();
+                None
+            }
+            ResolveEvent::Prefetch { items, selected } => {
+                let config = self.config.load();
+                let window = config.editor.completion.resolve_prefetch_window;
+                self.trigger = Some(
+                    items
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(idx, item)| !item.resolved && selected.abs_diff(*idx) <= window)
+                        .map(|(_, item)| item)
+                        .collect(),
+                );
+                Some(Instant::now() + config.editor.completion.resolve_debounce)
+            }
+        }
+    }
+
+    fn finish_debounce(&mut self) {
+        let Some(items) = self.trigger.take() else {
+            return;
+        };
+        if items.is_empty() {
+            return;
+        }
+        let (tx, rx) = cancelation();
+        self.request = Some(tx);
+        let version = self.version.clone();
+        let initial_version = version.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+        dispatch_blocking(move |editor, compositor| {
+            resolve_completion_items(editor, compositor, items, version, initial_version, rx)
+        });
+    }
+}
+
+fn resolve_completion_items(
+    editor: &mut Editor,
+    _compositor: &mut Compositor,
+    items: Vec<CompletionItem>,
+    version: Arc<AtomicUsize>,
+    initial_version: usize,
+    cancel: CancelRx,
+) {
+    let futures: FuturesUnordered<_> = items
+        .into_iter()
+        .filter_map(|item| {
+            let CompletionProvider::Lsp(provider) = item.provider else {
+                return None;
+            };
+            let ls = editor.language_server_by_id(provider)?;
+            let supports_resolve = matches!(
+                ls.capabilities().completion_provider,
+                Some(lsp::CompletionOptions {
+                    resolve_provider: Some(true),
+                    ..
+                })
+            );
+            if !supports_resolve {
+                return None;
+            }
+            let response = ls.resolve_completion_item(&item.item)?;
+            Some(async move {
+                let json = response.await.ok()?;
+                let resolved: lsp::CompletionItem = serde_json::from_value(json).ok()?;
+                Some((item, resolved))
+            })
+        })
+        .collect();
+
+    let resolve = async move {
+        pin!(futures);
+        while let Some(resolved) = futures.next().await {
+            let Some((item, resolved)) = resolved else {
+                continue;
+            };
+            if version.load(atomic::Ordering::Relaxed) != initial_version {
+                break;
+            }
+            let version = version.clone();
+            dispatch(move |_editor, compositor| {
+                if version.load(atomic::Ordering::Relaxed) != initial_version {
+                    return;
+                }
+                let editor_view = compositor.find::<ui::EditorView>().unwrap();
+                let Some(completion) = &mut editor_view.completion else {
+                    return;
+                };
+                completion.replace_item(item, resolved);
+            })
+            .await;
+        }
+    };
+    tokio::spawn(cancelable_future(resolve, cancel));
+}